@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+
+use crate::entry::FeedEntry;
+
+/// Identifies a bundle of feeds that are merged into a single combined
+/// digest rather than posted independently, one message per member feed.
+#[derive(Clone, Debug)]
+pub struct FeedGroupMeta {
+    pub title: String,
+    pub link: String,
+}
+
+/// Merges entries fetched from every member URL of a bundle into a single
+/// chronologically-sorted (newest first) stream, de-duplicated by link
+/// (falling back to title when a feed entry has no link).
+pub fn merge_entries(per_url_entries: Vec<Vec<FeedEntry>>) -> Vec<FeedEntry> {
+    let mut seen = HashSet::new();
+    let mut merged: Vec<FeedEntry> = per_url_entries
+        .into_iter()
+        .flatten()
+        .filter(|entry| {
+            let key = if entry.link.is_empty() { entry.title.clone() } else { entry.link.clone() };
+            seen.insert(key)
+        })
+        .collect();
+    merged.sort_by(|left, right| right.published.cmp(&left.published));
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(link: &str, title: &str, published: &str) -> FeedEntry {
+        FeedEntry {
+            link: link.to_string(),
+            title: title.to_string(),
+            published: published.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn dedups_by_link_across_urls() {
+        let merged = merge_entries(vec![
+            vec![entry("https://a.example/1", "A", "2024-01-02")],
+            vec![entry("https://a.example/1", "A duplicate", "2024-01-02")],
+        ]);
+        assert_eq!(merged.len(), 1);
+    }
+
+    #[test]
+    fn falls_back_to_title_when_link_is_empty() {
+        let merged = merge_entries(vec![vec![entry("", "Same Title", "2024-01-01"), entry("", "Same Title", "2024-01-02")]]);
+        assert_eq!(merged.len(), 1);
+    }
+
+    #[test]
+    fn entries_with_all_empty_keys_still_dedup_together() {
+        let merged = merge_entries(vec![vec![entry("", "", "2024-01-01"), entry("", "", "2024-01-02")]]);
+        assert_eq!(merged.len(), 1);
+    }
+
+    #[test]
+    fn sorts_newest_first() {
+        let merged = merge_entries(vec![vec![
+            entry("https://a.example/1", "old", "2024-01-01"),
+            entry("https://a.example/2", "new", "2024-06-01"),
+        ]]);
+        assert_eq!(merged[0].title, "new");
+        assert_eq!(merged[1].title, "old");
+    }
+
+    #[test]
+    fn stable_order_when_published_values_are_equal() {
+        let merged = merge_entries(vec![vec![
+            entry("https://a.example/1", "first", "2024-01-01"),
+            entry("https://a.example/2", "second", "2024-01-01"),
+        ]]);
+        assert_eq!(merged[0].title, "first");
+        assert_eq!(merged[1].title, "second");
+    }
+}
@@ -0,0 +1,65 @@
+use redis::{AsyncCommands, Client, RedisError};
+use serde::{Deserialize, Serialize};
+
+/// Conditional-fetch metadata and the last known body for a single feed URL,
+/// persisted in Redis so fetches survive process restarts.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// Stores and retrieves [`CachedResponse`]s in Redis, keyed by feed URL and
+/// expired after `cache_ttl` seconds.
+pub struct FeedCache {
+    client: Client,
+}
+
+impl FeedCache {
+    pub fn new(redis_url: &str) -> Result<Self, RedisError> {
+        Ok(Self {
+            client: Client::open(redis_url)?,
+        })
+    }
+
+    fn key(url: &str) -> String {
+        format!("telefeed:cache:{}", url)
+    }
+
+    pub async fn get(&self, url: &str) -> Result<Option<CachedResponse>, RedisError> {
+        let mut conn = self.client.get_async_connection().await?;
+        let raw: Option<Vec<u8>> = conn.get(Self::key(url)).await?;
+        Ok(raw.and_then(|raw| serde_json::from_slice(&raw).ok()))
+    }
+
+    pub async fn store(&self, url: &str, cached: &CachedResponse, cache_ttl: u64) -> Result<(), RedisError> {
+        let mut conn = self.client.get_async_connection().await?;
+        let raw = serde_json::to_vec(cached).unwrap_or_default();
+        conn.set_ex(Self::key(url), raw, cache_ttl).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_response_roundtrips_through_json() {
+        let cached = CachedResponse {
+            etag: Some("\"abc123\"".to_string()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+            body: b"<rss></rss>".to_vec(),
+        };
+        let raw = serde_json::to_vec(&cached).unwrap();
+        let restored: CachedResponse = serde_json::from_slice(&raw).unwrap();
+        assert_eq!(restored.etag, cached.etag);
+        assert_eq!(restored.last_modified, cached.last_modified);
+        assert_eq!(restored.body, cached.body);
+    }
+
+    #[test]
+    fn cache_key_is_namespaced_by_url() {
+        assert_eq!(FeedCache::key("https://example.com/feed.xml"), "telefeed:cache:https://example.com/feed.xml");
+    }
+}
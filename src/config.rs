@@ -14,17 +14,33 @@ use tgbot::{
 };
 use tokio::fs;
 
+use crate::bundle::FeedGroupMeta;
+use crate::filter::{Filter, FilterError, RawFilter};
+use crate::template::DEFAULT_MESSAGE_FORMAT;
+
 const DEFAULT_INCLUDE_FEED_TITLE: bool = false;
 const DEFAULT_REQUEST_TIMEOUT: u64 = 1200;
+const DEFAULT_CACHE_TTL: u64 = 900;
 
 #[derive(Deserialize)]
 struct RawConfig {
     token: String,
     proxy: Option<String>,
     redis_url: String,
-    feeds: HashMap<String, Vec<RawFeedConfig>>,
+    feeds: HashMap<String, Vec<RawFeedSource>>,
     include_feed_title: Option<bool>,
     request_timeout: Option<u64>,
+    message_format: Option<String>,
+    cache_ttl: Option<u64>,
+}
+
+/// A single entry under a chat's `feeds` list: either one plain source, or a
+/// named [`RawFeedGroup`] bundling several sources into one combined digest.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawFeedSource {
+    Group(RawFeedGroup),
+    Single(RawFeedConfig),
 }
 
 #[derive(Deserialize)]
@@ -33,6 +49,29 @@ struct RawFeedConfig {
     kind: FeedKind,
     include_feed_title: Option<bool>,
     request_timeout: Option<u64>,
+    message_format: Option<String>,
+    cache_ttl: Option<u64>,
+    #[serde(default)]
+    filters: Vec<RawFilter>,
+    proxy: Option<String>,
+}
+
+/// Several source URLs bundled under a single identity, so their entries are
+/// merged into one chronologically-sorted digest instead of one message per
+/// source. See [`crate::bundle::merge_entries`] for the merge itself.
+#[derive(Deserialize)]
+struct RawFeedGroup {
+    title: String,
+    link: String,
+    urls: Vec<String>,
+    kind: FeedKind,
+    include_feed_title: Option<bool>,
+    request_timeout: Option<u64>,
+    message_format: Option<String>,
+    cache_ttl: Option<u64>,
+    #[serde(default)]
+    filters: Vec<RawFilter>,
+    proxy: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -50,23 +89,77 @@ impl Config {
             .await
             .map_err(|err| ConfigError::ReadFile(path.to_owned(), err))?;
         let raw: RawConfig = serde_yaml::from_slice(&data).map_err(ConfigError::ParseYaml)?;
+        Self::from_raw(raw)
+    }
+
+    /// Resolves a parsed [`RawConfig`] into a [`Config`]: applies chat-id
+    /// parsing, per-feed default/override resolution, filter compilation,
+    /// and proxy validation. Split out from [`Config::from_file`] so the
+    /// merge/precedence logic can be exercised without touching the
+    /// filesystem.
+    fn from_raw(raw: RawConfig) -> Result<Self, ConfigError> {
         let default_include_feed_title = raw.include_feed_title.unwrap_or(DEFAULT_INCLUDE_FEED_TITLE);
         let default_request_timeout = raw.request_timeout.unwrap_or(DEFAULT_REQUEST_TIMEOUT);
+        let default_cache_ttl = raw.cache_ttl.unwrap_or(DEFAULT_CACHE_TTL);
+        let default_message_format = raw.message_format;
         let mut feeds = Vec::new();
         for (key, raw_feeds) in raw.feeds {
             let chat_id = match key.parse::<Integer>() {
                 Ok(chat_id) => ChatId::from(chat_id),
                 Err(_) => ChatId::from(key),
             };
-            for raw_feed in raw_feeds {
-                let include_feed_title = raw_feed.include_feed_title.unwrap_or(default_include_feed_title);
-                let request_timeout = raw_feed.request_timeout.unwrap_or(default_request_timeout);
+            for raw_source in raw_feeds {
+                let (urls, kind, include_feed_title, request_timeout, message_format, cache_ttl, raw_filters, proxy, group) =
+                    match raw_source {
+                        RawFeedSource::Single(raw_feed) => (
+                            vec![raw_feed.url],
+                            raw_feed.kind,
+                            raw_feed.include_feed_title,
+                            raw_feed.request_timeout,
+                            raw_feed.message_format,
+                            raw_feed.cache_ttl,
+                            raw_feed.filters,
+                            raw_feed.proxy,
+                            None,
+                        ),
+                        RawFeedSource::Group(raw_group) => (
+                            raw_group.urls,
+                            raw_group.kind,
+                            raw_group.include_feed_title,
+                            raw_group.request_timeout,
+                            raw_group.message_format,
+                            raw_group.cache_ttl,
+                            raw_group.filters,
+                            raw_group.proxy,
+                            Some(FeedGroupMeta {
+                                title: raw_group.title,
+                                link: raw_group.link,
+                            }),
+                        ),
+                    };
+                let include_feed_title = include_feed_title.unwrap_or(default_include_feed_title);
+                let request_timeout = request_timeout.unwrap_or(default_request_timeout);
+                let cache_ttl = cache_ttl.unwrap_or(default_cache_ttl);
+                let message_format = message_format
+                    .or_else(|| default_message_format.clone())
+                    .unwrap_or_else(|| message_format_for_include_feed_title(include_feed_title));
+                let filters = raw_filters
+                    .into_iter()
+                    .map(Filter::compile)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(ConfigError::Filter)?;
+                let proxy = proxy.map(validate_proxy).transpose()?;
                 feeds.push(FeedConfig {
                     chat_id: chat_id.clone(),
-                    url: raw_feed.url,
-                    kind: raw_feed.kind,
+                    urls,
+                    kind,
                     include_feed_title,
                     request_timeout: Duration::from_secs(request_timeout),
+                    cache_ttl: Duration::from_secs(cache_ttl),
+                    message_format,
+                    filters,
+                    proxy,
+                    group,
                 });
             }
         }
@@ -95,21 +188,53 @@ impl Config {
     }
 }
 
+/// Validates a per-feed proxy string through the same parser the top-level
+/// `proxy` uses, so a bad per-feed override is caught at load time as
+/// [`ConfigError::ProxyAddress`] instead of failing silently at fetch time.
+fn validate_proxy(proxy: String) -> Result<String, ParseProxyError> {
+    ApiConfig::new(String::new()).proxy(proxy.clone())?;
+    Ok(proxy)
+}
+
+/// Maps the legacy `include_feed_title` flag onto an equivalent `message_format`
+/// template, so configs that never set `message_format` keep working unchanged.
+fn message_format_for_include_feed_title(include_feed_title: bool) -> String {
+    if include_feed_title {
+        "{feed_title}: {title}\n{link}".to_string()
+    } else {
+        DEFAULT_MESSAGE_FORMAT.to_string()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct FeedConfig {
     pub chat_id: ChatId,
-    pub url: String,
+    /// Source URLs to fetch and, for a bundle, merge into one digest. A
+    /// plain (non-bundled) feed always has exactly one entry here.
+    pub urls: Vec<String>,
     pub kind: FeedKind,
     pub include_feed_title: bool,
+    pub message_format: String,
     pub request_timeout: Duration,
+    pub cache_ttl: Duration,
+    pub filters: Vec<Filter>,
+    pub proxy: Option<String>,
+    /// Present when this `FeedConfig` bundles several `urls` together;
+    /// carries the digest's own title/link identity for rendering.
+    pub group: Option<FeedGroupMeta>,
 }
 
-#[derive(Copy, Clone, Debug, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
 pub enum FeedKind {
     #[serde(rename = "rss")]
     Rss,
     #[serde(rename = "atom")]
     Atom,
+    #[serde(rename = "json_feed")]
+    JsonFeed,
+    /// Detect the format from the response `Content-Type` header and body, see [`crate::feed::detect_kind`].
+    #[serde(rename = "auto")]
+    Auto,
 }
 
 #[derive(Debug)]
@@ -117,6 +242,7 @@ pub enum ConfigError {
     ParseYaml(YamlError),
     ProxyAddress(ParseProxyError),
     ReadFile(PathBuf, IoError),
+    Filter(FilterError),
 }
 
 impl From<ParseProxyError> for ConfigError {
@@ -131,6 +257,7 @@ impl Error for ConfigError {
             ConfigError::ParseYaml(err) => Some(err),
             ConfigError::ProxyAddress(err) => Some(err),
             ConfigError::ReadFile(_, err) => Some(err),
+            ConfigError::Filter(err) => Some(err),
         }
     }
 }
@@ -141,6 +268,189 @@ impl fmt::Display for ConfigError {
             ConfigError::ParseYaml(err) => write!(out, "failed to parse YAML: {}", err),
             ConfigError::ProxyAddress(err) => write!(out, "bad proxy address: {}", err),
             ConfigError::ReadFile(path, err) => write!(out, "failed to read a file '{}': {}", path.display(), err),
+            ConfigError::Filter(err) => write!(out, "{}", err),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(yaml: &str) -> RawConfig {
+        serde_yaml::from_str(yaml).expect("test fixture should parse")
+    }
+
+    fn base_config() -> Config {
+        Config::from_raw(raw(
+            r#"
+            token: "bot-token"
+            redis_url: "redis://localhost"
+            feeds:
+              "123":
+                - url: "https://example.com/feed.xml"
+                  kind: auto
+            "#,
+        ))
+        .expect("base fixture should resolve")
+    }
+
+    #[test]
+    fn single_source_resolves_to_one_feed_with_no_group() {
+        let config = base_config();
+        let feeds = config.into_feeds();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].urls, vec!["https://example.com/feed.xml".to_string()]);
+        assert!(feeds[0].group.is_none());
+    }
+
+    #[test]
+    fn grouped_source_resolves_to_one_feed_with_all_urls_and_group_meta() {
+        let config = Config::from_raw(raw(
+            r#"
+            token: "bot-token"
+            redis_url: "redis://localhost"
+            feeds:
+              "123":
+                - title: "Digest"
+                  link: "https://example.com/digest"
+                  urls:
+                    - "https://a.example/feed.xml"
+                    - "https://b.example/feed.xml"
+                  kind: auto
+            "#,
+        ))
+        .unwrap();
+        let feeds = config.into_feeds();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].urls.len(), 2);
+        let group = feeds[0].group.as_ref().expect("grouped source should carry group meta");
+        assert_eq!(group.title, "Digest");
+        assert_eq!(group.link, "https://example.com/digest");
+    }
+
+    #[test]
+    fn per_feed_overrides_win_over_global_defaults() {
+        let config = Config::from_raw(raw(
+            r#"
+            token: "bot-token"
+            redis_url: "redis://localhost"
+            cache_ttl: 900
+            request_timeout: 1200
+            feeds:
+              "123":
+                - url: "https://example.com/feed.xml"
+                  kind: auto
+                  cache_ttl: 60
+                  request_timeout: 30
+            "#,
+        ))
+        .unwrap();
+        let feeds = config.into_feeds();
+        assert_eq!(feeds[0].cache_ttl, Duration::from_secs(60));
+        assert_eq!(feeds[0].request_timeout, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn global_defaults_apply_when_feed_has_no_override() {
+        let config = Config::from_raw(raw(
+            r#"
+            token: "bot-token"
+            redis_url: "redis://localhost"
+            cache_ttl: 300
+            feeds:
+              "123":
+                - url: "https://example.com/feed.xml"
+                  kind: auto
+            "#,
+        ))
+        .unwrap();
+        assert_eq!(config.into_feeds()[0].cache_ttl, Duration::from_secs(300));
+    }
+
+    #[test]
+    fn unset_cache_ttl_falls_back_to_the_builtin_default() {
+        let feeds = base_config().into_feeds();
+        assert_eq!(feeds[0].cache_ttl, Duration::from_secs(DEFAULT_CACHE_TTL));
+        assert_eq!(feeds[0].request_timeout, Duration::from_secs(DEFAULT_REQUEST_TIMEOUT));
+    }
+
+    #[test]
+    fn include_feed_title_true_maps_to_the_feed_title_template() {
+        let config = Config::from_raw(raw(
+            r#"
+            token: "bot-token"
+            redis_url: "redis://localhost"
+            feeds:
+              "123":
+                - url: "https://example.com/feed.xml"
+                  kind: auto
+                  include_feed_title: true
+            "#,
+        ))
+        .unwrap();
+        assert_eq!(config.into_feeds()[0].message_format, "{feed_title}: {title}\n{link}");
+    }
+
+    #[test]
+    fn explicit_message_format_overrides_include_feed_title() {
+        let config = Config::from_raw(raw(
+            r#"
+            token: "bot-token"
+            redis_url: "redis://localhost"
+            feeds:
+              "123":
+                - url: "https://example.com/feed.xml"
+                  kind: auto
+                  include_feed_title: true
+                  message_format: "{title}"
+            "#,
+        ))
+        .unwrap();
+        assert_eq!(config.into_feeds()[0].message_format, "{title}");
+    }
+
+    #[test]
+    fn unset_include_feed_title_keeps_the_builtin_default_template() {
+        let feeds = base_config().into_feeds();
+        assert_eq!(feeds[0].message_format, DEFAULT_MESSAGE_FORMAT);
+    }
+
+    #[test]
+    fn numeric_chat_key_parses_as_an_integer_chat_id() {
+        let feeds = base_config().into_feeds();
+        assert_eq!(feeds[0].chat_id, ChatId::from(123));
+    }
+
+    #[test]
+    fn non_numeric_chat_key_is_kept_as_a_username() {
+        let config = Config::from_raw(raw(
+            r#"
+            token: "bot-token"
+            redis_url: "redis://localhost"
+            feeds:
+              "@some_channel":
+                - url: "https://example.com/feed.xml"
+                  kind: auto
+            "#,
+        ))
+        .unwrap();
+        assert_eq!(config.into_feeds()[0].chat_id, ChatId::from("@some_channel".to_string()));
+    }
+
+    #[test]
+    fn malformed_per_feed_proxy_surfaces_as_proxy_address_error() {
+        let result = Config::from_raw(raw(
+            r#"
+            token: "bot-token"
+            redis_url: "redis://localhost"
+            feeds:
+              "123":
+                - url: "https://example.com/feed.xml"
+                  kind: auto
+                  proxy: "not a proxy url"
+            "#,
+        ));
+        assert!(matches!(result, Err(ConfigError::ProxyAddress(_))));
+    }
+}
@@ -0,0 +1,15 @@
+/// A normalized feed entry, independent of the underlying feed format.
+///
+/// Fields are plain `String`s rather than the richer types some feed
+/// parsers expose (e.g. parsed timestamps) so they can be substituted
+/// directly into message templates.
+#[derive(Clone, Debug, Default)]
+pub struct FeedEntry {
+    pub feed_title: String,
+    pub title: String,
+    pub link: String,
+    pub author: String,
+    pub summary: String,
+    pub published: String,
+    pub categories: Vec<String>,
+}
@@ -0,0 +1,190 @@
+use feed_rs::model::Feed as RawFeed;
+use feed_rs::parser;
+
+use crate::config::FeedKind;
+use crate::entry::FeedEntry;
+
+/// The wire-format family a [`FeedKind`] belongs to. `Rss` and `Atom` are
+/// both plain XML and `feed_rs` parses either uninstructed, so enforcing the
+/// difference between them buys nothing and only rejects valid feeds sniffed
+/// the "wrong" way around; only a genuine XML/JSON mismatch indicates a feed
+/// that the declared `kind` cannot actually parse.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Family {
+    Xml,
+    Json,
+}
+
+fn family(kind: FeedKind) -> Family {
+    match kind {
+        FeedKind::Rss | FeedKind::Atom => Family::Xml,
+        FeedKind::JsonFeed => Family::Json,
+        FeedKind::Auto => unreachable!("Auto has no family of its own"),
+    }
+}
+
+/// Sniffs the response `Content-Type` header and the leading bytes of the
+/// body to decide which format [`FeedKind::Auto`] should dispatch to.
+/// Looks at the XML root element (`<feed` for Atom) rather than collapsing
+/// every `xml`-ish `Content-Type` to RSS, since Atom feeds are commonly
+/// served with a generic `text/xml`/`application/xml` type. Falls back to
+/// [`FeedKind::Rss`] when nothing more specific matches.
+pub fn detect_kind(content_type: Option<&str>, body: &[u8]) -> FeedKind {
+    if let Some(content_type) = content_type {
+        let content_type = content_type.to_ascii_lowercase();
+        if content_type.contains("json") {
+            return FeedKind::JsonFeed;
+        }
+    }
+    let trimmed = leading_bytes(body);
+    if trimmed.starts_with(b"{") {
+        return FeedKind::JsonFeed;
+    }
+    if has_atom_root(trimmed) {
+        return FeedKind::Atom;
+    }
+    FeedKind::Rss
+}
+
+fn leading_bytes(body: &[u8]) -> &[u8] {
+    let start = body.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(body.len());
+    &body[start..]
+}
+
+/// Looks for an `<feed` root element within the first KB of the body,
+/// skipping over an optional XML prolog/comments/doctype, to tell an Atom
+/// feed apart from RSS/RDF without relying on the `Content-Type` header.
+fn has_atom_root(body: &[u8]) -> bool {
+    let window = &body[..body.len().min(1024)];
+    let text = String::from_utf8_lossy(window);
+    let mut rest = text.as_ref();
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        if let Some(comment) = rest.strip_prefix("!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        if rest.starts_with('?') || rest.starts_with('!') {
+            continue;
+        }
+        let name_end = rest.find(|c: char| c.is_whitespace() || c == '>' || c == '/').unwrap_or(rest.len());
+        return &rest[..name_end] == "feed";
+    }
+    false
+}
+
+#[derive(Debug)]
+pub enum FeedError {
+    Parse(parser::ParseFeedError),
+    /// The declared `kind`'s wire format (XML vs JSON) does not match what
+    /// `detect_kind` sniffed, meaning the declared `kind` cannot parse this
+    /// response at all, e.g. `kind: json_feed` pointed at an RSS feed.
+    KindMismatch { declared: FeedKind, detected: FeedKind },
+}
+
+impl std::fmt::Display for FeedError {
+    fn fmt(&self, out: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FeedError::Parse(err) => write!(out, "failed to parse feed: {}", err),
+            FeedError::KindMismatch { declared, detected } => {
+                write!(out, "feed declared as {:?} but detected as {:?}", declared, detected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FeedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FeedError::Parse(err) => Some(err),
+            FeedError::KindMismatch { .. } => None,
+        }
+    }
+}
+
+/// Parses a feed response into a list of normalized [`FeedEntry`] values.
+///
+/// `feed_rs` auto-senses the wire format itself, so `kind` is not used to
+/// pick a parser; instead, for a non-[`FeedKind::Auto`] `kind`, its wire
+/// family (XML vs JSON) is checked against the sniffed format and rejected
+/// on mismatch, so a `kind: json_feed` pointed at an XML feed surfaces as an
+/// error instead of silently failing to parse.
+///
+/// `title_override` names the entries' `feed_title` (e.g. a bundle's own
+/// group title); when `None`, the feed's own parsed title is used instead.
+pub fn parse(
+    title_override: Option<&str>,
+    kind: FeedKind,
+    content_type: Option<&str>,
+    body: &[u8],
+) -> Result<Vec<FeedEntry>, FeedError> {
+    let detected = detect_kind(content_type, body);
+    if kind != FeedKind::Auto && family(kind) != family(detected) {
+        return Err(FeedError::KindMismatch { declared: kind, detected });
+    }
+    let feed: RawFeed = parser::parse(body).map_err(FeedError::Parse)?;
+    let feed_title = title_override.map(str::to_string).unwrap_or_else(|| feed.title.map(|text| text.content).unwrap_or_default());
+    Ok(feed.entries.into_iter().map(|entry| to_feed_entry(&feed_title, entry)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_type_json_wins() {
+        assert_eq!(detect_kind(Some("application/feed+json"), b""), FeedKind::JsonFeed);
+    }
+
+    #[test]
+    fn atom_root_wins_over_generic_xml_content_type() {
+        assert_eq!(detect_kind(Some("text/xml"), b"<?xml version=\"1.0\"?><feed xmlns=\"http://www.w3.org/2005/Atom\"></feed>"), FeedKind::Atom);
+        assert_eq!(detect_kind(Some("application/xml"), b"<feed></feed>"), FeedKind::Atom);
+    }
+
+    #[test]
+    fn rss_root_wins_over_generic_xml_content_type() {
+        assert_eq!(detect_kind(Some("text/xml"), b"<?xml version=\"1.0\"?><rss></rss>"), FeedKind::Rss);
+    }
+
+    #[test]
+    fn leading_byte_sniff_detects_json_without_content_type() {
+        assert_eq!(detect_kind(None, b"  {\"version\":\"https://jsonfeed.org/version/1\"}"), FeedKind::JsonFeed);
+    }
+
+    #[test]
+    fn falls_back_to_rss_when_nothing_matches() {
+        assert_eq!(detect_kind(None, b"<?xml version=\"1.0\"?><rss></rss>"), FeedKind::Rss);
+        assert_eq!(detect_kind(Some("text/plain"), b""), FeedKind::Rss);
+    }
+
+    #[test]
+    fn rss_declared_against_atom_body_no_longer_mismatches() {
+        assert_eq!(family(FeedKind::Rss), family(FeedKind::Atom));
+    }
+
+    #[test]
+    fn json_and_xml_families_differ() {
+        assert_ne!(family(FeedKind::JsonFeed), family(FeedKind::Rss));
+    }
+}
+
+fn to_feed_entry(feed_title: &str, entry: feed_rs::model::Entry) -> FeedEntry {
+    FeedEntry {
+        feed_title: feed_title.to_string(),
+        title: entry.title.map(|text| text.content).unwrap_or_default(),
+        link: entry.links.first().map(|link| link.href.clone()).unwrap_or_default(),
+        author: entry
+            .authors
+            .first()
+            .map(|person| person.name.clone())
+            .unwrap_or_default(),
+        summary: entry.summary.map(|text| text.content).unwrap_or_default(),
+        published: entry
+            .published
+            .or(entry.updated)
+            .map(|date| date.to_rfc3339())
+            .unwrap_or_default(),
+        categories: entry.categories.into_iter().map(|category| category.term).collect(),
+    }
+}
@@ -0,0 +1,236 @@
+use regex::Regex;
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::entry::FeedEntry;
+
+/// One step in a feed's filter pipeline, applied in declaration order.
+/// A step that drops the entry (a `KeepMatching` that does not match, or a
+/// `DropMatching` that does) short-circuits the rest of the pipeline for it.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RawFilter {
+    KeepMatching { patterns: Vec<String> },
+    DropMatching { patterns: Vec<String> },
+    FullText,
+}
+
+/// A compiled [`RawFilter`], ready to be applied to entries without
+/// re-parsing patterns on every run.
+#[derive(Clone, Debug)]
+pub enum Filter {
+    KeepMatching(Vec<Regex>),
+    DropMatching(Vec<Regex>),
+    FullText,
+}
+
+#[derive(Debug)]
+pub struct FilterError(regex::Error);
+
+impl std::fmt::Display for FilterError {
+    fn fmt(&self, out: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(out, "invalid filter pattern: {}", self.0)
+    }
+}
+
+impl std::error::Error for FilterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl Filter {
+    pub fn compile(raw: RawFilter) -> Result<Self, FilterError> {
+        match raw {
+            RawFilter::KeepMatching { patterns } => Ok(Filter::KeepMatching(compile_patterns(patterns)?)),
+            RawFilter::DropMatching { patterns } => Ok(Filter::DropMatching(compile_patterns(patterns)?)),
+            RawFilter::FullText => Ok(Filter::FullText),
+        }
+    }
+}
+
+/// Prefix that opts a pattern into regex matching; without it, a pattern is
+/// treated as a literal case-insensitive substring so keywords containing
+/// regex metacharacters (e.g. `"C++"`, `"$9.99"`) match as written.
+const REGEX_PREFIX: &str = "regex:";
+
+/// Compiles each pattern into a case-insensitive regex: a bare pattern is
+/// escaped and matched as a literal substring, while a `regex:`-prefixed
+/// pattern is compiled as-is.
+fn compile_patterns(patterns: Vec<String>) -> Result<Vec<Regex>, FilterError> {
+    patterns
+        .into_iter()
+        .map(|pattern| {
+            let source = match pattern.strip_prefix(REGEX_PREFIX) {
+                Some(regex) => regex.to_string(),
+                None => regex::escape(&pattern),
+            };
+            Regex::new(&format!("(?i){}", source)).map_err(FilterError)
+        })
+        .collect()
+}
+
+fn matches_any(patterns: &[Regex], entry: &FeedEntry) -> bool {
+    patterns.iter().any(|pattern| pattern.is_match(&entry.title) || pattern.is_match(&entry.summary))
+}
+
+/// Markers that indicate a feed truncated its summary rather than providing
+/// the full article text, e.g. "Read on for the rest of the story...".
+const TRUNCATION_MARKERS: [&str; 3] = ["...", "…", "[...]"];
+
+/// Whether `summary` looks like it was cut short rather than being the
+/// complete article body, per the `full_text` filter's "only expand
+/// truncated summaries" contract.
+fn looks_truncated(summary: &str) -> bool {
+    let trimmed = summary.trim_end();
+    trimmed.is_empty() || TRUNCATION_MARKERS.iter().any(|marker| trimmed.ends_with(marker))
+}
+
+/// Runs `entry` through `filters` in order, fetching (through `client`, so
+/// it honors the feed's own proxy) and substituting the full article body
+/// whenever a `full_text` step is reached and the summary looks truncated.
+/// Returns `None` if a `keep_matching`/`drop_matching` step drops it,
+/// short-circuiting the remaining filters; otherwise returns the (possibly
+/// rewritten) entry.
+pub async fn apply(client: &Client, filters: &[Filter], mut entry: FeedEntry) -> Option<FeedEntry> {
+    for filter in filters {
+        match filter {
+            Filter::KeepMatching(patterns) => {
+                if !matches_any(patterns, &entry) {
+                    return None;
+                }
+            }
+            Filter::DropMatching(patterns) => {
+                if matches_any(patterns, &entry) {
+                    return None;
+                }
+            }
+            Filter::FullText => {
+                if looks_truncated(&entry.summary) {
+                    if let Ok(article) = fetch_full_text(client, &entry.link).await {
+                        entry.summary = article;
+                    }
+                }
+            }
+        }
+    }
+    Some(entry)
+}
+
+/// Fetches `link` through `client` and extracts the main article body,
+/// stripping `script`/`style` content before flattening the rest to plain
+/// text. Used by the `full_text` filter to expand summary-only feeds.
+async fn fetch_full_text(client: &Client, link: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let html = client.get(link).send().await?.text().await?;
+    let html = strip_script_and_style(&html);
+    let document = scraper::Html::parse_document(&html);
+    let article_selector = scraper::Selector::parse("article").unwrap();
+    let body_selector = scraper::Selector::parse("body").unwrap();
+    let root = document
+        .select(&article_selector)
+        .next()
+        .or_else(|| document.select(&body_selector).next());
+    Ok(root.map(|element| element.text().collect::<Vec<_>>().join(" ")).unwrap_or_default())
+}
+
+/// Removes `<script>...</script>` and `<style>...</style>` blocks before
+/// parsing, since `scraper`'s text extraction otherwise includes their
+/// contents verbatim.
+fn strip_script_and_style(html: &str) -> String {
+    let pattern = Regex::new(r"(?is)<(script|style)\b[^>]*>.*?</\1>").expect("static regex is valid");
+    pattern.replace_all(html, "").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, summary: &str) -> FeedEntry {
+        FeedEntry {
+            title: title.to_string(),
+            summary: summary.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn client() -> Client {
+        Client::new()
+    }
+
+    #[test]
+    fn literal_pattern_matches_regex_metacharacters_as_written() {
+        let patterns = compile_patterns(vec!["C++".to_string()]).unwrap();
+        assert!(matches_any(&patterns, &entry("Learning C++", "")));
+        assert!(!matches_any(&patterns, &entry("Learning C", "")));
+    }
+
+    #[test]
+    fn literal_pattern_is_case_insensitive() {
+        let patterns = compile_patterns(vec!["sponsored".to_string()]).unwrap();
+        assert!(matches_any(&patterns, &entry("SPONSORED post", "")));
+    }
+
+    #[test]
+    fn regex_prefixed_pattern_is_compiled_as_regex() {
+        let patterns = compile_patterns(vec!["regex:^ad-\\d+$".to_string()]).unwrap();
+        assert!(matches_any(&patterns, &entry("ad-42", "")));
+        assert!(!matches_any(&patterns, &entry("ad-42x", "")));
+    }
+
+    #[test]
+    fn matches_any_checks_title_and_summary() {
+        let patterns = compile_patterns(vec!["breaking".to_string()]).unwrap();
+        assert!(matches_any(&patterns, &entry("", "breaking news")));
+    }
+
+    #[test]
+    fn ellipsis_suffixes_look_truncated() {
+        assert!(looks_truncated("Read more..."));
+        assert!(looks_truncated("Read more…"));
+        assert!(looks_truncated("Read more [...]"));
+        assert!(looks_truncated(""));
+    }
+
+    #[test]
+    fn complete_looking_summary_is_not_truncated() {
+        assert!(!looks_truncated("The full story, in its entirety."));
+    }
+
+    #[test]
+    fn strips_script_and_style_blocks() {
+        let html = "<body><script>evil()</script><style>.x{}</style><article>Real text</article></body>";
+        let stripped = strip_script_and_style(html);
+        assert!(!stripped.contains("evil()"));
+        assert!(!stripped.contains(".x{}"));
+        assert!(stripped.contains("Real text"));
+    }
+
+    #[tokio::test]
+    async fn keep_matching_drops_entry_that_does_not_match() {
+        let filters = vec![Filter::KeepMatching(compile_patterns(vec!["rust".to_string()]).unwrap())];
+        assert!(apply(&client(), &filters, entry("Python release", "")).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn drop_matching_short_circuits_remaining_filters() {
+        let filters = vec![
+            Filter::DropMatching(compile_patterns(vec!["sponsored".to_string()]).unwrap()),
+            Filter::KeepMatching(compile_patterns(vec!["this never runs".to_string()]).unwrap()),
+        ];
+        assert!(apply(&client(), &filters, entry("Sponsored post", "")).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn entry_surviving_all_filters_is_returned() {
+        let filters = vec![Filter::KeepMatching(compile_patterns(vec!["rust".to_string()]).unwrap())];
+        let result = apply(&client(), &filters, entry("Rust 2.0 released", "")).await;
+        assert_eq!(result.unwrap().title, "Rust 2.0 released");
+    }
+
+    #[tokio::test]
+    async fn full_text_filter_leaves_complete_summary_untouched() {
+        let filters = vec![Filter::FullText];
+        let result = apply(&client(), &filters, entry("Title", "A complete summary with no ellipsis.")).await;
+        assert_eq!(result.unwrap().summary, "A complete summary with no ellipsis.");
+    }
+}
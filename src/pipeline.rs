@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use reqwest::header::{HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
+
+use crate::bundle;
+use crate::cache::{CachedResponse, FeedCache};
+use crate::config::FeedConfig;
+use crate::entry::FeedEntry;
+use crate::feed::{self, FeedError};
+use crate::filter;
+use crate::template;
+
+#[derive(Debug)]
+pub enum PipelineError {
+    Cache(redis::RedisError),
+    Request(reqwest::Error),
+    Feed(FeedError),
+}
+
+impl std::fmt::Display for PipelineError {
+    fn fmt(&self, out: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PipelineError::Cache(err) => write!(out, "cache error: {}", err),
+            PipelineError::Request(err) => write!(out, "request error: {}", err),
+            PipelineError::Feed(err) => write!(out, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for PipelineError {}
+
+impl From<redis::RedisError> for PipelineError {
+    fn from(err: redis::RedisError) -> Self {
+        PipelineError::Cache(err)
+    }
+}
+
+impl From<reqwest::Error> for PipelineError {
+    fn from(err: reqwest::Error) -> Self {
+        PipelineError::Request(err)
+    }
+}
+
+impl From<FeedError> for PipelineError {
+    fn from(err: FeedError) -> Self {
+        PipelineError::Feed(err)
+    }
+}
+
+/// Fetches `url`, replaying the last known `ETag`/`Last-Modified` from
+/// `cache` as `If-None-Match`/`If-Modified-Since`. Returns `Ok(None)` on a
+/// `304 Not Modified` response, skipping parsing entirely; otherwise stores
+/// the new response in `cache` (expiring after `cache_ttl`) and returns its
+/// body along with the response `Content-Type`.
+pub async fn fetch_with_cache(
+    client: &Client,
+    cache: &FeedCache,
+    url: &str,
+    cache_ttl: std::time::Duration,
+) -> Result<Option<(Vec<u8>, Option<String>)>, PipelineError> {
+    let cached = cache.get(url).await?;
+    let mut request = client.get(url);
+    if let Some(cached) = &cached {
+        if let Some(etag) = &cached.etag {
+            if let Ok(value) = HeaderValue::from_str(etag) {
+                request = request.header(IF_NONE_MATCH, value);
+            }
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            if let Ok(value) = HeaderValue::from_str(last_modified) {
+                request = request.header(IF_MODIFIED_SINCE, value);
+            }
+        }
+    }
+    let response = request.send().await?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let last_modified = response
+        .headers()
+        .get(LAST_MODIFIED)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = response.bytes().await?.to_vec();
+    cache
+        .store(
+            url,
+            &CachedResponse {
+                etag,
+                last_modified,
+                body: body.clone(),
+            },
+            cache_ttl.as_secs(),
+        )
+        .await?;
+    Ok(Some((body, content_type)))
+}
+
+/// Builds the `reqwest::Client` a feed's fetches should go through: routed
+/// via `proxy` when set, or the default direct client otherwise. Feeds route
+/// independently of each other and of the bot's own Telegram traffic, which
+/// uses the top-level `proxy` via [`crate::config::Config::get_api_config`].
+pub fn build_client(proxy: Option<&str>) -> Result<Client, reqwest::Error> {
+    let mut builder = Client::builder();
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    builder.build()
+}
+
+/// Caches one `reqwest::Client` per distinct proxy configuration, so
+/// `poll_feed` reuses a feed's connection pool/TLS state across poll cycles
+/// instead of rebuilding a client on every fetch. Feeds that share a `proxy`
+/// (including feeds with no proxy at all) share a client.
+#[derive(Default)]
+pub struct ClientCache {
+    clients: Mutex<HashMap<String, Client>>,
+}
+
+impl ClientCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_or_build(&self, proxy: Option<&str>) -> Result<Client, reqwest::Error> {
+        let key = proxy.unwrap_or_default().to_string();
+        let mut clients = self.clients.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(client) = clients.get(&key) {
+            return Ok(client.clone());
+        }
+        let client = build_client(proxy)?;
+        clients.insert(key, client.clone());
+        Ok(client)
+    }
+}
+
+/// Fetches `url` through `client` and parses it against `feed.kind`, naming
+/// entries `title_override` when set (a bundle's own group title) or falling
+/// back to the feed's own parsed title otherwise. Returns an empty list on a
+/// `304 Not Modified`, since there is nothing new to parse.
+pub async fn fetch_and_parse(
+    client: &Client,
+    cache: &FeedCache,
+    feed: &FeedConfig,
+    title_override: Option<&str>,
+    url: &str,
+) -> Result<Vec<FeedEntry>, PipelineError> {
+    match fetch_with_cache(client, cache, url, feed.cache_ttl).await? {
+        Some((body, content_type)) => Ok(feed::parse(title_override, feed.kind, content_type.as_deref(), &body)?),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Runs every entry through `feed.filters`, dropping the ones a
+/// `keep_matching`/`drop_matching` step rejects and expanding the rest via
+/// `full_text` (fetched through `client`, so it honors the feed's own proxy)
+/// where configured.
+pub async fn apply_filters(client: &Client, feed: &FeedConfig, entries: Vec<FeedEntry>) -> Vec<FeedEntry> {
+    let mut kept = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if let Some(entry) = filter::apply(client, &feed.filters, entry).await {
+            kept.push(entry);
+        }
+    }
+    kept
+}
+
+/// Renders every entry of `entries` through `feed.message_format`, producing
+/// the exact strings handed to the Telegram send call for `feed.chat_id`.
+/// This is the last step of the per-feed poll cycle, after fetch/parse/filter.
+pub fn render_entries(feed: &FeedConfig, entries: &[FeedEntry]) -> Vec<String> {
+    entries.iter().map(|entry| template::render(&feed.message_format, entry)).collect()
+}
+
+/// Runs one full poll cycle for `feed`: fetches every member of `feed.urls`
+/// (a plain feed has exactly one), merges them into a single
+/// chronologically-sorted (newest first), de-duplicated stream (a plain
+/// feed's own entries are sorted and de-duplicated the same way), applies
+/// `feed.filters`, and renders the survivors
+/// through `feed.message_format`. Returns the message strings ready to send
+/// to `feed.chat_id`, with a `"{title}\n{link}"` digest header prepended
+/// when `feed.group` carries its own title/link identity.
+pub async fn poll_feed(clients: &ClientCache, cache: &FeedCache, feed: &FeedConfig) -> Result<Vec<String>, PipelineError> {
+    let client = clients.get_or_build(feed.proxy.as_deref())?;
+    let title_override = feed.group.as_ref().map(|group| group.title.as_str());
+    let mut per_url_entries = Vec::with_capacity(feed.urls.len());
+    for url in &feed.urls {
+        per_url_entries.push(fetch_and_parse(&client, cache, feed, title_override, url).await?);
+    }
+    let merged = bundle::merge_entries(per_url_entries);
+    let filtered = apply_filters(&client, feed, merged).await;
+    let mut rendered = render_entries(feed, &filtered);
+    if let Some(group) = &feed.group {
+        rendered.insert(0, format!("{}\n{}", group.title, group.link));
+    }
+    Ok(rendered)
+}
@@ -0,0 +1,63 @@
+use crate::entry::FeedEntry;
+
+/// Template used when neither `message_format` nor `include_feed_title` is configured.
+pub const DEFAULT_MESSAGE_FORMAT: &str = "{title}\n{link}";
+
+/// Renders a message template by substituting `{placeholder}` tokens with
+/// fields from `entry`. Unknown placeholders are left untouched; known
+/// placeholders with no value become an empty string.
+pub fn render(template: &str, entry: &FeedEntry) -> String {
+    let mut out = template.to_string();
+    for (placeholder, value) in [
+        ("{feed_title}", entry.feed_title.as_str()),
+        ("{title}", entry.title.as_str()),
+        ("{link}", entry.link.as_str()),
+        ("{author}", entry.author.as_str()),
+        ("{summary}", entry.summary.as_str()),
+        ("{published}", entry.published.as_str()),
+    ] {
+        out = out.replace(placeholder, value);
+    }
+    out = out.replace("{categories}", &entry.categories.join(", "));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> FeedEntry {
+        FeedEntry {
+            feed_title: "My Feed".to_string(),
+            title: "Hello".to_string(),
+            link: "https://example.com".to_string(),
+            categories: vec!["news".to_string(), "tech".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_format_uses_title_and_link() {
+        assert_eq!(render(DEFAULT_MESSAGE_FORMAT, &entry()), "Hello\nhttps://example.com");
+    }
+
+    #[test]
+    fn missing_field_becomes_empty_string() {
+        assert_eq!(render("[{author}][{summary}]", &entry()), "[][]");
+    }
+
+    #[test]
+    fn unknown_placeholder_is_left_alone() {
+        assert_eq!(render("{title} {not_a_field}", &entry()), "Hello {not_a_field}");
+    }
+
+    #[test]
+    fn categories_are_joined_with_commas() {
+        assert_eq!(render("{categories}", &entry()), "news, tech");
+    }
+
+    #[test]
+    fn empty_categories_render_as_empty_string() {
+        assert_eq!(render("[{categories}]", &FeedEntry::default()), "[]");
+    }
+}